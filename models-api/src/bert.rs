@@ -101,6 +101,14 @@ pub struct Embeddings {
     data: Vec<Vec<f64>>,
 }
 
+impl Embeddings {
+    /// Consume the embeddings, returning the underlying per-sentence vectors in the order
+    /// the input sentences were given.
+    pub fn into_vec(self) -> Vec<Vec<f64>> {
+        self.data
+    }
+}
+
 #[derive(serde::Serialize, serde::Deserialize)]
 pub struct Params {
     sentences: Vec<String>,
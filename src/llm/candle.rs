@@ -0,0 +1,121 @@
+use anyhow::{Error as E, Result};
+use candle::{DType, Device, Tensor};
+use candle_nn::VarBuilder;
+use candle_transformers::generation::LogitsProcessor;
+use candle_transformers::models::llama::{Cache, Config, Llama, LlamaConfig};
+use tokenizers::Tokenizer;
+
+use crate::llm::openai::Message;
+use crate::llm::{LLMResponse, LLM};
+
+const EOS_TOKEN: &str = "</s>";
+
+/// A local, offline backend that implements [`LLM`] by running autoregressive decoding on a
+/// causal LM loaded via `candle`, so orca can run fully offline with no API key, while
+/// keeping the same orchestration interface as [`OpenAIClient`](crate::llm::openai::OpenAIClient).
+pub struct CandleLLM {
+    model: Llama,
+    tokenizer: Tokenizer,
+    config: Config,
+    device: Device,
+
+    /// What sampling temperature to use, mirroring `OpenAIClient::with_temperature`.
+    temperature: f32,
+
+    /// Nucleus sampling cutoff, mirroring `OpenAIClient::with_top_p`.
+    top_p: f32,
+
+    /// The maximum number of tokens to generate before stopping, absent an EOS token.
+    max_tokens: usize,
+}
+
+impl CandleLLM {
+    /// Load a causal LM's safetensors + tokenizer from the Hugging Face Hub, the same way
+    /// `models_api::bert::Model::from_api` loads its embedding model.
+    pub async fn from_api(model_id: Option<String>, revision: Option<String>) -> Result<Self> {
+        let device = Device::Cpu;
+        let default_model = "TinyLlama/TinyLlama-1.1B-Chat-v1.0".to_string();
+        let default_revision = "main".to_string();
+        let (model_id, revision) = match (model_id, revision) {
+            (Some(model_id), Some(revision)) => (model_id, revision),
+            (Some(model_id), None) => (model_id, "main".to_string()),
+            (None, Some(revision)) => (default_model, revision),
+            (None, None) => (default_model, default_revision),
+        };
+
+        let repo = hf_hub::Repo::with_revision(model_id, hf_hub::RepoType::Model, revision);
+        let api = hf_hub::api::tokio::Api::new()?;
+        let api = api.repo(repo);
+
+        let config_filename = api.get("config.json").await?;
+        let tokenizer_filename = api.get("tokenizer.json").await?;
+        let weights_filename = api.get("model.safetensors").await?;
+
+        let config: LlamaConfig = serde_json::from_str(&std::fs::read_to_string(config_filename)?)?;
+        let config: Config = config.into_config(false);
+        let tokenizer = Tokenizer::from_file(tokenizer_filename).map_err(E::msg)?;
+
+        let vb = unsafe { VarBuilder::from_mmaped_safetensors(&[weights_filename], DType::F32, &device)? };
+        let model = Llama::load(vb, &config)?;
+
+        Ok(Self { model, tokenizer, config, device, temperature: 1.0, top_p: 1.0, max_tokens: 1024 })
+    }
+
+    /// What sampling temperature to use, between 0 and 2, mirroring `OpenAIClient::with_temperature`.
+    pub fn with_temperature(mut self, temperature: f32) -> Self {
+        self.temperature = temperature;
+        self
+    }
+
+    /// An alternative to sampling with temperature, mirroring `OpenAIClient::with_top_p`.
+    pub fn with_top_p(mut self, top_p: f32) -> Self {
+        self.top_p = top_p;
+        self
+    }
+
+    /// The maximum number of tokens to generate, mirroring `OpenAIClient::with_max_tokens`.
+    pub fn with_max_tokens(mut self, max_tokens: usize) -> Self {
+        self.max_tokens = max_tokens;
+        self
+    }
+
+    /// Render the conversation into the single prompt string the model is decoded from.
+    fn render_prompt(messages: &[Message]) -> String {
+        messages.iter().map(Message::to_string).collect::<Vec<_>>().join("\n")
+    }
+}
+
+#[async_trait::async_trait(?Send)]
+impl LLM for CandleLLM {
+    async fn generate(&self, messages: &[Message]) -> Result<LLMResponse> {
+        let prompt = Self::render_prompt(messages);
+        let tokens = self.tokenizer.encode(prompt, true).map_err(E::msg)?;
+        let mut tokens = tokens.get_ids().to_vec();
+
+        let eos_token = self.tokenizer.token_to_id(EOS_TOKEN);
+        let mut logits_processor = LogitsProcessor::new(299792458, Some(self.temperature as f64), Some(self.top_p as f64));
+        // A fresh cache per call, so the KV state and positions of one call never carry over
+        // into the next.
+        let mut cache = Cache::new(true, DType::F32, &self.config, &self.device)?;
+        let mut generated_tokens = Vec::new();
+
+        for index in 0..self.max_tokens {
+            let context_size = if index == 0 { tokens.len() } else { 1 };
+            let start = tokens.len() - context_size;
+            let input = Tensor::new(&tokens[start..], &self.device)?.unsqueeze(0)?;
+            let logits = self.model.forward(&input, start, &mut cache)?;
+            let logits = logits.squeeze(0)?;
+            let next_token = logits_processor.sample(&logits)?;
+
+            tokens.push(next_token);
+            generated_tokens.push(next_token);
+
+            if eos_token == Some(next_token) {
+                break;
+            }
+        }
+
+        let content = self.tokenizer.decode(&generated_tokens, true).map_err(E::msg)?;
+        Ok(LLMResponse::new(content))
+    }
+}
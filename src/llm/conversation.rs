@@ -0,0 +1,131 @@
+use crate::llm::openai::Message;
+use crate::llm::LLM;
+use anyhow::Result;
+
+/// A reusable multi-turn chat session that owns the growing history of [`Message`]s and
+/// keeps a prompt's context across turns, so callers no longer have to re-render the full
+/// `Vec<Message>` by hand on every call.
+pub struct Conversation {
+    messages: Vec<Message>,
+
+    /// A rough cap on the size of the rolling history, measured with a simple whitespace
+    /// token estimate. When exceeded, the oldest non-system messages are evicted, keeping
+    /// any leading system message pinned.
+    max_history_tokens: Option<usize>,
+}
+
+impl Conversation {
+    /// Create an empty conversation with no history cap.
+    pub fn new() -> Self {
+        Self { messages: Vec::new(), max_history_tokens: None }
+    }
+
+    /// Cap the rolling history to roughly `max_history_tokens` tokens, evicting the oldest
+    /// non-system messages once the estimate is exceeded.
+    pub fn with_max_history_tokens(mut self, max_history_tokens: usize) -> Self {
+        self.max_history_tokens = Some(max_history_tokens);
+        self
+    }
+
+    /// The messages accumulated so far, oldest first.
+    pub fn history(&self) -> &[Message] {
+        &self.messages
+    }
+
+    /// Append a system message (typically used once, to set the assistant's instructions).
+    pub fn add_system(&mut self, message: &str) {
+        self.messages.push(Message::new("system".into(), message));
+        self.enforce_history_cap();
+    }
+
+    /// Append a user message to the history without sending it.
+    pub fn add_user(&mut self, message: &str) {
+        self.messages.push(Message::new("user".into(), message));
+        self.enforce_history_cap();
+    }
+
+    /// Append the user's turn, send the full history to `llm`, then push the assistant's
+    /// reply back into the history so the next `send` includes it as context.
+    pub async fn send(&mut self, llm: &impl LLM, message: &str) -> Result<Message> {
+        self.add_user(message);
+
+        let response = llm.generate(&self.messages).await?;
+        let assistant_message = Message::new("assistant".into(), response.get_response_content());
+        self.messages.push(assistant_message.clone());
+        self.enforce_history_cap();
+
+        Ok(assistant_message)
+    }
+
+    /// Evict the oldest non-system messages while the rolling token estimate exceeds
+    /// `max_history_tokens`, keeping any leading system message pinned.
+    fn enforce_history_cap(&mut self) {
+        let Some(max_history_tokens) = self.max_history_tokens else {
+            return;
+        };
+
+        while estimate_tokens(&self.messages) > max_history_tokens {
+            let evict_at = self.messages.iter().position(|message| message.role.to_string() != "system");
+            match evict_at {
+                Some(index) => {
+                    self.messages.remove(index);
+                }
+                None => break,
+            }
+        }
+    }
+}
+
+impl Default for Conversation {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A simple whitespace-based heuristic token counter, good enough for deciding when the
+/// rolling history is approaching the model's context window without pulling in a real
+/// tokenizer.
+fn estimate_tokens(messages: &[Message]) -> usize {
+    messages.iter().map(|message| message.message.split_whitespace().count()).sum()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn no_cap_keeps_every_message() {
+        let mut conversation = Conversation::new();
+        for turn in 0..20 {
+            conversation.add_user(&format!("turn {turn}"));
+        }
+        assert_eq!(conversation.history().len(), 20);
+    }
+
+    #[test]
+    fn cap_evicts_oldest_user_messages_first() {
+        let mut conversation = Conversation::new().with_max_history_tokens(4);
+        conversation.add_user("one two");
+        conversation.add_user("three four");
+        conversation.add_user("five six");
+
+        let history = conversation.history();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].message, "three four");
+        assert_eq!(history[1].message, "five six");
+    }
+
+    #[test]
+    fn cap_keeps_leading_system_message_pinned() {
+        let mut conversation = Conversation::new().with_max_history_tokens(5);
+        conversation.add_system("be concise");
+        conversation.add_user("one two three");
+        conversation.add_user("four five six");
+
+        let history = conversation.history();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].role.to_string(), "system");
+        assert_eq!(history[0].message, "be concise");
+        assert_eq!(history[1].message, "four five six");
+    }
+}
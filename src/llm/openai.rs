@@ -1,10 +1,15 @@
+use std::collections::HashMap;
 use std::fmt::{self, Display, Formatter};
+use std::pin::Pin;
 
 use super::request::RequestMessages;
 use crate::llm::LLM;
 use anyhow::Result;
 pub use async_openai::config::{Config, OpenAIConfig};
-use async_openai::types::{CreateChatCompletionRequest, CreateChatCompletionRequestArgs, Role as R};
+use async_openai::types::{
+    CreateChatCompletionRequest, CreateChatCompletionRequestArgs, CreateChatCompletionStreamResponse, Role as R,
+};
+use futures::{Stream, StreamExt};
 use serde::Serialize;
 
 use super::LLMResponse;
@@ -67,13 +72,12 @@ impl Display for Message {
     }
 }
 
-pub struct OpenAIClient {
+pub struct OpenAIClient<C: Config = OpenAIConfig> {
     /// Client member for the OpenAI API. This client is a wrapper around the async-openai crate, with additional functionality to
-    /// support LLM orchestration.
-    client: async_openai::Client<OpenAIConfig>,
-
-    /// The prompt to use for the OpenAI API
-    prompt: Option<Vec<Message>>,
+    /// support LLM orchestration. Generic over `Config` so callers can point at any
+    /// OpenAI-compatible endpoint (Azure, a local text-generation server, ...) rather than
+    /// being hardwired to the public OpenAI API.
+    client: async_openai::Client<C>,
 
     /// ID of the model to use.
     /// See the [model endpoint compatibility](https://platform.openai.com/docs/models/model-endpoint-compatibility) table for details on which models work with the Chat API.
@@ -101,27 +105,54 @@ pub struct OpenAIClient {
     ///
     /// The total length of input tokens and generated tokens is limited by the model's context length. [Example Python code](https://github.com/openai/openai-cookbook/blob/main/examples/How_to_count_tokens_with_tiktoken.ipynb) for counting tokens.
     max_tokens: u16,
+
+    /// Up to 4 sequences where the API will stop generating further tokens. Useful for cutting
+    /// the completion off cleanly at a user-defined delimiter (e.g. when formatting tool calls).
+    stop: Option<Vec<String>>,
+
+    /// Modify the likelihood of specified tokens appearing in the completion, mapping a token
+    /// id to a bias value in `-100..=100`. `-100` effectively bans the token, `100` effectively
+    /// forces it.
+    logit_bias: Option<HashMap<u32, i32>>,
 }
 
-impl Default for OpenAIClient {
+impl Default for OpenAIClient<OpenAIConfig> {
     fn default() -> Self {
         Self {
             client: async_openai::Client::new(),
-            prompt: None,
             model: "gpt-3.5-turbo".to_string(),
             temperature: 1.0,
             top_p: 1.0,
             stream: false,
             max_tokens: 1024u16,
+            stop: None,
+            logit_bias: None,
         }
     }
 }
 
-impl OpenAIClient {
-    /// Create a new OpenAI client
+impl OpenAIClient<OpenAIConfig> {
+    /// Create a new OpenAI client, talking to the public OpenAI API.
     pub fn new() -> Self {
         Self::default()
     }
+}
+
+impl<C: Config> OpenAIClient<C> {
+    /// Create a client from a custom `Config`, e.g. `OpenAIConfig::new().with_api_base(...)`
+    /// for Azure OpenAI or a self-hosted OpenAI-compatible server.
+    pub fn with_config(config: C) -> Self {
+        Self {
+            client: async_openai::Client::with_config(config),
+            model: "gpt-3.5-turbo".to_string(),
+            temperature: 1.0,
+            top_p: 1.0,
+            stream: false,
+            max_tokens: 1024u16,
+            stop: None,
+            logit_bias: None,
+        }
+    }
 
     /// Set model to use
     /// e.g. "davinci", "gpt-3.5-turbo"
@@ -130,14 +161,6 @@ impl OpenAIClient {
         self
     }
 
-    /// Set prompt to use
-    /// e.g. "What is the capital of France?"
-    /// This is the prompt that will be used to generate the response.
-    pub fn with_prompt(mut self, prompt: Vec<Message>) -> Self {
-        self.prompt = Some(prompt);
-        self
-    }
-
     /// What sampling temperature to use, between 0 and 2. Higher values like 0.8 will make the output more random,
     /// while lower values like 0.2 will make it more focused and deterministic.
     pub fn with_temperature(mut self, temperature: f32) -> Self {
@@ -164,30 +187,98 @@ impl OpenAIClient {
         self
     }
 
+    /// Up to 4 sequences where the API will stop generating further tokens.
+    /// An empty vector is a no-op, leaving any previously configured stop sequences untouched.
+    pub fn with_stop(mut self, stop: Vec<String>) -> Self {
+        if stop.is_empty() {
+            return self;
+        }
+        self.stop = Some(stop);
+        self
+    }
+
+    /// Modify the likelihood of specified tokens appearing in the completion.
+    /// Maps a token id to a bias value, which must be in `-100..=100`.
+    pub fn with_logit_bias(mut self, logit_bias: HashMap<u32, i32>) -> Self {
+        self.logit_bias = Some(logit_bias);
+        self
+    }
+
     /// Generate a request for the OpenAI API and set the parameters
     pub fn generate_request(&self, messages: &[Message]) -> Result<CreateChatCompletionRequest> {
-        Ok(CreateChatCompletionRequestArgs::default()
+        let mut request = CreateChatCompletionRequestArgs::default();
+        request
             .model(self.model.clone())
             .max_tokens(self.max_tokens)
             .temperature(self.temperature)
             .top_p(self.top_p)
             .stream(self.stream)
-            .messages(RequestMessages::from(messages.to_owned()))
-            .build()?)
+            .messages(RequestMessages::from(messages.to_owned()));
+
+        if let Some(stop) = &self.stop {
+            request.stop(stop.clone());
+        }
+
+        if let Some(logit_bias) = &self.logit_bias {
+            for (token, bias) in logit_bias {
+                if !(-100..=100).contains(bias) {
+                    anyhow::bail!("logit_bias for token {token} must be between -100 and 100, got {bias}");
+                }
+            }
+            let logit_bias = logit_bias.iter().map(|(token, bias)| (token.to_string(), (*bias).into())).collect::<HashMap<String, serde_json::Value>>();
+            request.logit_bias(logit_bias);
+        }
+
+        Ok(request.build()?)
     }
 }
 
 // Now implement these traits for your LLM types
 #[async_trait::async_trait(?Send)]
-impl LLM for OpenAIClient {
-    async fn generate(&self) -> Result<LLMResponse> {
-        let request = self.generate_request(&self.prompt.unwrap())?;
+impl<C: Config> LLM for OpenAIClient<C> {
+    async fn generate(&self, messages: &[Message]) -> Result<LLMResponse> {
+        let request = self.generate_request(messages)?;
 
         let res = self.client.chat().create(request).await?;
         Ok(res.into())
     }
 }
 
+/// A partial delta of an assistant response, yielded incrementally while a streaming
+/// completion is in flight. Successive deltas should be concatenated to reconstruct the
+/// full response, mirroring the ChatGPT-style token-by-token output.
+#[derive(Debug, Clone, Default)]
+pub struct LLMResponseDelta {
+    /// The incremental piece of assistant content carried by this chunk, if any.
+    /// `None` for chunks that only carry metadata (e.g. the final chunk with a finish reason).
+    pub content: Option<String>,
+}
+
+impl From<CreateChatCompletionStreamResponse> for LLMResponseDelta {
+    fn from(response: CreateChatCompletionStreamResponse) -> Self {
+        let content = response.choices.into_iter().next().and_then(|choice| choice.delta.content);
+        Self { content }
+    }
+}
+
+/// Streaming counterpart to [`LLM`], for backends that can yield incremental assistant
+/// deltas instead of blocking until the full completion is ready.
+#[async_trait::async_trait(?Send)]
+pub trait StreamingLLM {
+    /// Generate a response, yielding partial deltas as they arrive rather than waiting
+    /// for the full completion. Callers should have set `stream` via [`OpenAIClient::with_stream`].
+    async fn generate_stream(&self, messages: &[Message]) -> Result<Pin<Box<dyn Stream<Item = Result<LLMResponseDelta>>>>>;
+}
+
+#[async_trait::async_trait(?Send)]
+impl<C: Config> StreamingLLM for OpenAIClient<C> {
+    async fn generate_stream(&self, messages: &[Message]) -> Result<Pin<Box<dyn Stream<Item = Result<LLMResponseDelta>>>>> {
+        let request = self.generate_request(messages)?;
+        let stream = self.client.chat().create_stream(request).await?;
+        Ok(Box::pin(stream.map(|chunk| Ok(LLMResponseDelta::from(chunk?)))))
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -210,4 +301,60 @@ mod test {
         let response = client.generate(&prompt).await.unwrap();
         assert!(response.get_response_content().to_lowercase().contains("berlin"));
     }
+
+    #[test]
+    fn with_stop_ignores_empty_vec() {
+        let client = OpenAIClient::new().with_stop(vec!["STOP".to_string()]).with_stop(vec![]);
+        assert_eq!(client.stop, Some(vec!["STOP".to_string()]));
+        let messages = Message::into_vec(vec![("user", "hi")]);
+        let request = client.generate_request(&messages).unwrap();
+        assert!(request.stop.is_some());
+    }
+
+    #[test]
+    fn logit_bias_out_of_range_is_rejected() {
+        let mut bias = HashMap::new();
+        bias.insert(42u32, 200);
+        let client = OpenAIClient::new().with_logit_bias(bias);
+        let messages = Message::into_vec(vec![("user", "hi")]);
+        assert!(client.generate_request(&messages).is_err());
+    }
+
+    #[test]
+    fn logit_bias_in_range_is_accepted() {
+        let mut bias = HashMap::new();
+        bias.insert(42u32, -50);
+        let client = OpenAIClient::new().with_logit_bias(bias);
+        let messages = Message::into_vec(vec![("user", "hi")]);
+        assert!(client.generate_request(&messages).is_ok());
+    }
+
+    fn stream_chunk(delta: serde_json::Value, finish_reason: Option<&str>) -> CreateChatCompletionStreamResponse {
+        serde_json::from_value(serde_json::json!({
+            "id": "chatcmpl-test",
+            "object": "chat.completion.chunk",
+            "created": 0,
+            "model": "gpt-3.5-turbo",
+            "choices": [{
+                "index": 0,
+                "delta": delta,
+                "finish_reason": finish_reason,
+            }],
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn delta_from_stream_response_extracts_content() {
+        let chunk = stream_chunk(serde_json::json!({ "content": "Hel" }), None);
+        let delta = LLMResponseDelta::from(chunk);
+        assert_eq!(delta.content.as_deref(), Some("Hel"));
+    }
+
+    #[test]
+    fn delta_from_stream_response_is_none_for_metadata_only_chunks() {
+        let chunk = stream_chunk(serde_json::json!({}), Some("stop"));
+        let delta = LLMResponseDelta::from(chunk);
+        assert_eq!(delta.content, None);
+    }
 }
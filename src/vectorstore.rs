@@ -0,0 +1,184 @@
+use std::collections::HashMap;
+
+use anyhow::Result;
+use models_api::bert::Model;
+
+use crate::llm::openai::{Config, Message, OpenAIClient};
+use crate::llm::{LLMResponse, LLM};
+
+/// Distance metric used to score how close a candidate embedding is to a query embedding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DistanceMetric {
+    /// Cosine similarity. Degrades to a plain dot product when both vectors are normalized.
+    Cosine,
+    /// Raw dot product, cheapest when embeddings are already normalized.
+    Dot,
+    /// Negative Euclidean distance, so that higher is still "closer" like the other metrics.
+    Euclidean,
+}
+
+struct Record {
+    id: String,
+    text: String,
+    embedding: Vec<f64>,
+}
+
+/// An in-memory store of `(id, text, embedding)` records, searchable by nearest-neighbor
+/// similarity. This is the minimal retrieval half of a retrieval-augmented-generation
+/// pipeline; pair it with [`Model::get_embeddings`] to ingest text and with
+/// [`VectorStore::assemble_context_message`] to feed hits back into an [`OpenAIClient`] prompt.
+pub struct VectorStore {
+    metric: DistanceMetric,
+    records: Vec<Record>,
+
+    /// Monotonically increasing counter used to assign ids in [`VectorStore::add_texts`], so
+    /// that ingesting documents across multiple calls doesn't collide on id "0", "1", ...
+    next_id: usize,
+}
+
+impl VectorStore {
+    /// Create an empty store scored with `metric`.
+    pub fn new(metric: DistanceMetric) -> Self {
+        Self { metric, records: Vec::new(), next_id: 0 }
+    }
+
+    /// Add a single `(id, text, embedding)` record.
+    pub fn add(&mut self, id: impl Into<String>, text: impl Into<String>, embedding: Vec<f64>) {
+        self.records.push(Record { id: id.into(), text: text.into(), embedding });
+    }
+
+    /// Embed `texts` in a batch via `model` and add each as a record, keyed by an id drawn
+    /// from the store's own counter so repeated calls (the normal way to ingest documents
+    /// incrementally) don't produce colliding ids.
+    pub fn add_texts(&mut self, model: &mut Model, texts: &[String]) -> Result<()> {
+        let embeddings = model.get_embeddings(texts, true)?.into_vec();
+        for (text, embedding) in texts.iter().zip(embeddings) {
+            let id = self.next_id;
+            self.next_id += 1;
+            self.add(id.to_string(), text.clone(), embedding);
+        }
+        Ok(())
+    }
+
+    /// Return the ids and scores of the `k` records closest to `query_embedding`, sorted
+    /// by descending score.
+    pub fn top_k(&self, query_embedding: &[f64], k: usize) -> Vec<(String, f64)> {
+        let mut scored: Vec<(String, f64)> =
+            self.records.iter().map(|record| (record.id.clone(), self.score(&record.embedding, query_embedding))).collect();
+        scored.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        scored.truncate(k);
+        scored
+    }
+
+    /// Embed `query` via `model` and return the `k` closest records.
+    pub fn similarity_search(&self, model: &mut Model, query: &str, k: usize) -> Result<Vec<(String, f64)>> {
+        let mut embeddings = model.get_embeddings(&[query.to_string()], true)?.into_vec();
+        let query_embedding = embeddings.remove(0);
+        Ok(self.top_k(&query_embedding, k))
+    }
+
+    /// Render the text of the given hits into a single system/context [`Message`], suitable
+    /// for prepending to a prompt before calling [`OpenAIClient::generate`].
+    pub fn assemble_context_message(&self, hits: &[(String, f64)]) -> Message {
+        let texts: HashMap<&str, &str> = self.records.iter().map(|record| (record.id.as_str(), record.text.as_str())).collect();
+        let context = hits.iter().filter_map(|(id, _)| texts.get(id.as_str())).copied().collect::<Vec<_>>().join("\n\n");
+        Message::new("system".into(), &format!("Use the following context to answer the question:\n\n{context}"))
+    }
+
+    fn score(&self, a: &[f64], b: &[f64]) -> f64 {
+        match self.metric {
+            DistanceMetric::Cosine => {
+                let dot = dot(a, b);
+                let norm = norm(a) * norm(b);
+                if norm == 0.0 {
+                    0.0
+                } else {
+                    dot / norm
+                }
+            }
+            DistanceMetric::Dot => dot(a, b),
+            DistanceMetric::Euclidean => -a.iter().zip(b).map(|(x, y)| (x - y).powi(2)).sum::<f64>().sqrt(),
+        }
+    }
+}
+
+fn dot(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+fn norm(a: &[f64]) -> f64 {
+    dot(a, a).sqrt()
+}
+
+/// Retrieve the `k` passages most relevant to `query`, assemble them into a context
+/// message, and generate a response from `client` grounded in that context — a minimal
+/// retrieval-augmented-generation pipeline.
+pub async fn generate_with_context<C: Config>(
+    store: &VectorStore,
+    model: &mut Model,
+    client: &OpenAIClient<C>,
+    query: &str,
+    k: usize,
+) -> Result<LLMResponse> {
+    let hits = store.similarity_search(model, query, k)?;
+    let context = store.assemble_context_message(&hits);
+    let messages = vec![context, Message::new("user".into(), query)];
+    client.generate(&messages).await
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn top_k_orders_by_descending_cosine_similarity() {
+        let mut store = VectorStore::new(DistanceMetric::Cosine);
+        store.add("exact", "exact match", vec![1.0, 0.0]);
+        store.add("orthogonal", "unrelated", vec![0.0, 1.0]);
+        store.add("close", "close match", vec![0.9, 0.1]);
+
+        let hits = store.top_k(&[1.0, 0.0], 2);
+
+        assert_eq!(hits.len(), 2);
+        assert_eq!(hits[0].0, "exact");
+        assert_eq!(hits[1].0, "close");
+        assert!(hits[0].1 > hits[1].1);
+    }
+
+    #[test]
+    fn top_k_truncates_to_k() {
+        let mut store = VectorStore::new(DistanceMetric::Dot);
+        store.add("a", "a", vec![1.0, 0.0]);
+        store.add("b", "b", vec![0.0, 1.0]);
+        store.add("c", "c", vec![1.0, 1.0]);
+
+        let hits = store.top_k(&[1.0, 1.0], 1);
+
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].0, "c");
+    }
+
+    #[test]
+    fn euclidean_prefers_the_nearest_point() {
+        let mut store = VectorStore::new(DistanceMetric::Euclidean);
+        store.add("near", "near", vec![1.0, 1.0]);
+        store.add("far", "far", vec![10.0, 10.0]);
+
+        let hits = store.top_k(&[0.0, 0.0], 2);
+
+        assert_eq!(hits[0].0, "near");
+        assert!(hits[0].1 > hits[1].1);
+    }
+
+    #[test]
+    fn assemble_context_message_resolves_each_hit_to_its_own_text() {
+        let mut store = VectorStore::new(DistanceMetric::Cosine);
+        store.add("0", "first batch's text", vec![1.0, 0.0]);
+        store.add("1", "second batch's text", vec![0.0, 1.0]);
+
+        let message = store.assemble_context_message(&[("1".to_string(), 1.0)]);
+
+        assert!(message.message.contains("second batch's text"));
+        assert!(!message.message.contains("first batch's text"));
+    }
+}